@@ -118,6 +118,419 @@ pub fn edit_dist(s1: &str, s2: &str) -> Option<AlignResult> {
     })
 }
 
+// Cells outside the band are treated as this "infinity" rather than u32::MAX
+// so that + 1 (and the delta in the recurrence below) can never overflow.
+const BANDED_INF: u32 = u32::MAX / 2;
+
+// Row-major storage for a diagonal band of width 2k+1: row `i` only holds
+// cells `lo[i]..lo[i] + rows[i].len()`, so both memory and fill time are
+// O(n*k) rather than O(n*m). `get` returns BANDED_INF for any column outside
+// the stored band.
+struct BandMatrix {
+    rows: Vec<Vec<u32>>,
+    lo: Vec<usize>,
+}
+
+impl BandMatrix {
+    fn get(&self, i: usize, j: usize) -> u32 {
+        let row = &self.rows[i];
+        if j < self.lo[i] || j - self.lo[i] >= row.len() {
+            BANDED_INF
+        } else {
+            row[j - self.lo[i]]
+        }
+    }
+
+    fn set(&mut self, i: usize, j: usize, val: u32) {
+        let offset = self.lo[i];
+        self.rows[i][j - offset] = val;
+    }
+}
+
+// Same walk as `traceback`, but reading through a `BandMatrix` instead of a
+// full rectangular one.
+fn traceback_banded(band: &BandMatrix, n: usize, m: usize) -> String {
+    if n == 0 && m == 0 {
+        return String::new();
+    }
+
+    let mut alignment = String::new();
+
+    let mut i = n;
+    let mut j = m;
+
+    while !(i == 0 && j == 0) {
+        if i == 0 {
+            alignment.push_str("I");
+            j -= 1;
+        } else if j == 0 {
+            alignment.push_str("D");
+            i -= 1;
+        } else {
+            let mut move_choices = vec![
+                ("M", band.get(i - 1, j - 1)),
+                ("I", band.get(i - 1, j)),
+                ("D", band.get(i, j - 1)),
+            ];
+            move_choices.sort_by_key(|k| k.1);
+            alignment.push_str(move_choices[0].0);
+            match move_choices[0].0 {
+                "M" => {
+                    i -= 1;
+                    j -= 1;
+                }
+                "I" => i -= 1,
+                "D" => j -= 1,
+                _ => unreachable!(),
+            }
+        }
+    }
+
+    let mut cigar = String::new();
+    let mut prev_char = alignment.chars().rev().next().unwrap();
+    let mut count = 0;
+    for c in alignment.chars().rev() {
+        if prev_char == c {
+            count = count + 1;
+        } else {
+            cigar.push_str(format!("{}{}", count, prev_char).as_str());
+            count = 1;
+        }
+        prev_char = c;
+    }
+    cigar.push_str(format!("{}{}", count, prev_char).as_str());
+
+    cigar
+}
+
+// Edit distance restricted to a diagonal band of width 2k+1, in O(n*k) time
+// and space - for callers who only care whether two strings are within k
+// edits of each other (e.g. deduping near-identical reads).
+pub fn edit_dist_banded(s1: &str, s2: &str, k: usize) -> Option<AlignResult> {
+    let s1_bytes = s1.as_bytes();
+    let s2_bytes = s2.as_bytes();
+    let n = s1_bytes.len();
+    let m = s2_bytes.len();
+
+    if (n as i64 - m as i64).unsigned_abs() as usize > k {
+        return None;
+    }
+
+    let band_lo = |i: usize| if i > k { i - k } else { 0 };
+    let band_hi = |i: usize| (i + k).min(m);
+
+    let mut lo = Vec::with_capacity(n + 1);
+    let mut rows = Vec::with_capacity(n + 1);
+    for i in 0..n + 1 {
+        let row_lo = band_lo(i);
+        let row_hi = band_hi(i);
+        lo.push(row_lo);
+        let width = row_hi.saturating_sub(row_lo).saturating_add(1);
+        rows.push(vec![BANDED_INF; width]);
+    }
+    let mut band = BandMatrix { rows, lo };
+
+    band.set(0, 0, 0);
+    for j in 1..(k.min(m) + 1) {
+        band.set(0, j, j as u32);
+    }
+    for i in 1..(k.min(n) + 1) {
+        band.set(i, 0, i as u32);
+    }
+
+    for i in 1..n + 1 {
+        let row_lo = band_lo(i);
+        let row_hi = band_hi(i);
+        if row_lo > row_hi {
+            return None;
+        }
+
+        let lo_j = row_lo.max(1);
+        let mut row_min = BANDED_INF;
+        for j in lo_j..row_hi + 1 {
+            let mut delta = 0;
+            if s1_bytes[i - 1] != s2_bytes[j - 1] {
+                delta = 1;
+            }
+
+            let diag = band.get(i - 1, j - 1) + delta;
+            let up = band.get(i - 1, j).saturating_add(1);
+            let left = band.get(i, j - 1).saturating_add(1);
+
+            let best = diag.min(up).min(left);
+            band.set(i, j, best);
+            row_min = row_min.min(best);
+        }
+
+        if row_min > k as u32 {
+            return None;
+        }
+    }
+
+    let edit_dist = band.get(n, m);
+    if edit_dist > k as u32 {
+        return None;
+    }
+
+    Some(AlignResult {
+        edit_dist,
+        cigar: traceback_banded(&band, n, m),
+    })
+}
+
+// Scoring parameters for affine-gap (Gotoh) alignment: match_score rewards
+// identical bases, the rest are penalties charged once (gap_open) or per
+// extra gap base (gap_extend).
+#[derive(Debug, Clone, Copy)]
+pub struct ScoringScheme {
+    pub match_score: i32,
+    pub mismatch: i32,
+    pub gap_open: i32,
+    pub gap_extend: i32,
+}
+
+impl ScoringScheme {
+    pub fn new(match_score: i32, mismatch: i32, gap_open: i32, gap_extend: i32) -> Self {
+        ScoringScheme {
+            match_score,
+            mismatch,
+            gap_open,
+            gap_extend,
+        }
+    }
+}
+
+impl Default for ScoringScheme {
+    fn default() -> Self {
+        ScoringScheme::new(1, 1, 2, 1)
+    }
+}
+
+// Result of an affine-gap alignment: the best score found and its CIGAR.
+#[derive(Debug)]
+pub struct AffineAlignResult {
+    pub score: i32,
+    pub cigar: String,
+}
+
+impl PartialEq for AffineAlignResult {
+    fn eq(&self, other: &Self) -> bool {
+        self.score == other.score && self.cigar == other.cigar
+    }
+}
+
+// Matrix a cell's best score came from, used to steer the Gotoh traceback.
+#[derive(Debug, Clone, Copy, PartialEq)]
+enum GapState {
+    Match,
+    GapInS1, // Ix: consumed a base of s1 only (vertical move)
+    GapInS2, // Iy: consumed a base of s2 only (horizontal move)
+}
+
+const NEG_INF: i32 = i32::MIN / 2;
+
+// Fill the three Gotoh matrices (M/Ix/Iy). In local mode M is floored at 0
+// so an alignment can restart anywhere (Smith-Waterman).
+fn fill_matrices(
+    s1: &[u8],
+    s2: &[u8],
+    scoring: &ScoringScheme,
+    local: bool,
+) -> (Vec<Vec<i32>>, Vec<Vec<i32>>, Vec<Vec<i32>>) {
+    let n = s1.len();
+    let m = s2.len();
+
+    let mut mat_m = vec![vec![NEG_INF; m + 1]; n + 1];
+    let mut ix = vec![vec![NEG_INF; m + 1]; n + 1];
+    let mut iy = vec![vec![NEG_INF; m + 1]; n + 1];
+
+    mat_m[0][0] = 0;
+    for i in 1..n + 1 {
+        ix[i][0] = if local {
+            0
+        } else {
+            -scoring.gap_open - (i as i32 - 1) * scoring.gap_extend
+        };
+        if local {
+            mat_m[i][0] = 0;
+        }
+    }
+    for j in 1..m + 1 {
+        iy[0][j] = if local {
+            0
+        } else {
+            -scoring.gap_open - (j as i32 - 1) * scoring.gap_extend
+        };
+        if local {
+            mat_m[0][j] = 0;
+        }
+    }
+
+    for i in 1..n + 1 {
+        for j in 1..m + 1 {
+            let sub = if s1[i - 1] == s2[j - 1] {
+                scoring.match_score
+            } else {
+                -scoring.mismatch
+            };
+            let diag_best = mat_m[i - 1][j - 1].max(ix[i - 1][j - 1]).max(iy[i - 1][j - 1]);
+            mat_m[i][j] = diag_best + sub;
+
+            ix[i][j] = (mat_m[i - 1][j] - scoring.gap_open).max(ix[i - 1][j] - scoring.gap_extend);
+            iy[i][j] = (mat_m[i][j - 1] - scoring.gap_open).max(iy[i][j - 1] - scoring.gap_extend);
+
+            if local {
+                mat_m[i][j] = mat_m[i][j].max(0);
+            }
+        }
+    }
+
+    (mat_m, ix, iy)
+}
+
+// Walk back through whichever matrix produced the score at (i, j), same
+// CIGAR collapsing as `traceback` above.
+fn traceback_affine(
+    mat_m: &Vec<Vec<i32>>,
+    ix: &Vec<Vec<i32>>,
+    iy: &Vec<Vec<i32>>,
+    s1: &[u8],
+    s2: &[u8],
+    scoring: &ScoringScheme,
+    mut i: usize,
+    mut j: usize,
+    local: bool,
+) -> String {
+    if i == 0 && j == 0 {
+        return String::new();
+    }
+
+    let best = mat_m[i][j].max(ix[i][j]).max(iy[i][j]);
+    let mut state = if mat_m[i][j] == best {
+        GapState::Match
+    } else if ix[i][j] == best {
+        GapState::GapInS1
+    } else {
+        GapState::GapInS2
+    };
+
+    let mut alignment = String::new();
+
+    while i > 0 || j > 0 {
+        if local && state == GapState::Match && mat_m[i][j] == 0 {
+            break;
+        }
+
+        if i == 0 {
+            alignment.push_str("D");
+            j -= 1;
+            state = GapState::GapInS2;
+            continue;
+        }
+        if j == 0 {
+            alignment.push_str("I");
+            i -= 1;
+            state = GapState::GapInS1;
+            continue;
+        }
+
+        match state {
+            GapState::Match => {
+                alignment.push_str("M");
+                let sub = if s1[i - 1] == s2[j - 1] {
+                    scoring.match_score
+                } else {
+                    -scoring.mismatch
+                };
+                let prev = mat_m[i][j] - sub;
+                i -= 1;
+                j -= 1;
+                state = if mat_m[i][j] == prev {
+                    GapState::Match
+                } else if ix[i][j] == prev {
+                    GapState::GapInS1
+                } else {
+                    GapState::GapInS2
+                };
+            }
+            GapState::GapInS1 => {
+                alignment.push_str("I");
+                let opened = mat_m[i - 1][j] - scoring.gap_open == ix[i][j];
+                i -= 1;
+                state = if opened { GapState::Match } else { GapState::GapInS1 };
+            }
+            GapState::GapInS2 => {
+                alignment.push_str("D");
+                let opened = mat_m[i][j - 1] - scoring.gap_open == iy[i][j];
+                j -= 1;
+                state = if opened { GapState::Match } else { GapState::GapInS2 };
+            }
+        }
+    }
+
+    if alignment.is_empty() {
+        return alignment;
+    }
+
+    let mut cigar = String::new();
+    let mut prev_char = alignment.chars().rev().next().unwrap();
+    let mut count = 0;
+    for c in alignment.chars().rev() {
+        if prev_char == c {
+            count = count + 1;
+        } else {
+            cigar.push_str(format!("{}{}", count, prev_char).as_str());
+            count = 1;
+        }
+        prev_char = c;
+    }
+    cigar.push_str(format!("{}{}", count, prev_char).as_str());
+
+    cigar
+}
+
+// Global affine-gap alignment (Needleman-Wunsch with Gotoh's recurrence).
+pub fn align_global(s1: &str, s2: &str, scoring: &ScoringScheme) -> AffineAlignResult {
+    let s1_bytes = s1.as_bytes();
+    let s2_bytes = s2.as_bytes();
+
+    let (mat_m, ix, iy) = fill_matrices(s1_bytes, s2_bytes, scoring, false);
+
+    let n = s1_bytes.len();
+    let m = s2_bytes.len();
+    let score = mat_m[n][m].max(ix[n][m]).max(iy[n][m]);
+    let cigar = traceback_affine(&mat_m, &ix, &iy, s1_bytes, s2_bytes, scoring, n, m, false);
+
+    AffineAlignResult { score, cigar }
+}
+
+// Local affine-gap alignment (Smith-Waterman with Gotoh's recurrence).
+pub fn align_local(s1: &str, s2: &str, scoring: &ScoringScheme) -> AffineAlignResult {
+    let s1_bytes = s1.as_bytes();
+    let s2_bytes = s2.as_bytes();
+
+    let (mat_m, ix, iy) = fill_matrices(s1_bytes, s2_bytes, scoring, true);
+
+    let mut best = 0;
+    let mut best_i = 0;
+    let mut best_j = 0;
+    for i in 0..s1_bytes.len() + 1 {
+        for j in 0..s2_bytes.len() + 1 {
+            if mat_m[i][j] > best {
+                best = mat_m[i][j];
+                best_i = i;
+                best_j = j;
+            }
+        }
+    }
+
+    let cigar = traceback_affine(
+        &mat_m, &ix, &iy, s1_bytes, s2_bytes, scoring, best_i, best_j, true,
+    );
+
+    AffineAlignResult { score: best, cigar }
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -191,4 +604,74 @@ mod tests {
             }
         );
     }
+
+    #[test]
+    fn banded_matches_full_dp_within_band() {
+        let s1 = "ACGTAAACAC";
+        let s2 = "ACGTAACAC";
+        let align_result = edit_dist_banded(s1, s2, 1).unwrap();
+        assert_eq!(
+            align_result,
+            AlignResult {
+                edit_dist: 1,
+                cigar: String::from("6M1I3M")
+            }
+        );
+    }
+
+    #[test]
+    fn banded_bails_on_length_difference_over_k() {
+        let s1 = "ACGTAAACAC";
+        let s2 = "ACGTAACAC";
+        assert_eq!(edit_dist_banded(s1, s2, 0), None);
+    }
+
+    #[test]
+    fn banded_bails_when_distance_exceeds_k() {
+        let s1 = "ACGTAAAAACCCAGGGCACACGTGGGGCACACACA";
+        let s2 = "ACGTCACACGTGGGGCACACACA";
+        assert_eq!(edit_dist_banded(s1, s2, 2), None);
+    }
+
+    #[test]
+    fn banded_handles_empty_strings() {
+        let align_result = edit_dist_banded("", "", 0).unwrap();
+        assert_eq!(
+            align_result,
+            AlignResult {
+                edit_dist: 0,
+                cigar: String::new()
+            }
+        );
+    }
+
+    #[test]
+    fn affine_global_discounts_long_gap() {
+        let s1 = "AAAACCCCCCCCCCAAAA";
+        let s2 = "AAAAAAAA";
+        let scoring = ScoringScheme::new(1, 1, 2, 1);
+        let align_result = align_global(s1, s2, &scoring);
+        assert_eq!(
+            align_result,
+            AffineAlignResult {
+                score: -3,
+                cigar: String::from("4M10I4M")
+            }
+        );
+    }
+
+    #[test]
+    fn affine_local_finds_exact_substring() {
+        let s1 = "XXXXXACGTACGTYYYYY";
+        let s2 = "ACGTACGT";
+        let scoring = ScoringScheme::new(1, 1, 2, 1);
+        let align_result = align_local(s1, s2, &scoring);
+        assert_eq!(
+            align_result,
+            AffineAlignResult {
+                score: 8,
+                cigar: String::from("8M")
+            }
+        );
+    }
 }
@@ -1,3 +1,4 @@
+use std::collections::{HashMap, HashSet, VecDeque};
 use std::env;
 use std::fs::File;
 use std::io::BufReader;
@@ -12,17 +13,31 @@ pub struct Config {
     pub filename: String,
     pub case_sensitive: bool,
     pub use_kmp: bool,
+    pub patterns_file: Option<String>,
+    pub prefilter: bool,
+    pub normalize_diacritics: bool,
+    pub fuzzy: bool,
 }
 
 impl Config {
     pub fn new(mut args: std::env::Args) -> Result<Config, &'static str> {
         args.next();
 
-        let query = match args.next() {
+        let first = match args.next() {
             Some(arg) => arg,
             None => return Err("Did not get a query string !"),
         };
 
+        let (query, patterns_file) = if first == "--patterns-file" {
+            let path = match args.next() {
+                Some(arg) => arg,
+                None => return Err("Did not get a patterns file path"),
+            };
+            (String::new(), Some(path))
+        } else {
+            (first, None)
+        };
+
         let filename = match args.next() {
             Some(arg) => arg,
             None => return Err("Did not get a file name"),
@@ -32,59 +47,239 @@ impl Config {
 
         let use_kmp = !env::var("USE_KMP").is_err();
 
+        let prefilter = !env::var("PREFILTER").is_err();
+
+        let normalize_diacritics = !env::var("NORMALIZE_DIACRITICS").is_err();
+
+        let fuzzy = !env::var("FUZZY").is_err();
+
         Ok(Config {
             query,
             filename,
             case_sensitive,
             use_kmp,
+            patterns_file,
+            prefilter,
+            normalize_diacritics,
+            fuzzy,
         })
     }
 }
 
 pub fn run(config: Config) -> Result<(), Box<dyn Error>> {
 
+    if let Some(patterns_path) = config.patterns_file {
+        let patterns_contents = std::fs::read_to_string(patterns_path)?;
+        let patterns: Vec<&str> = patterns_contents.lines().collect();
+
+        let f = File::open(config.filename)?;
+        let reader = BufReader::new(f);
+
+        for (pattern_idx, line) in ac_search(&patterns, reader) {
+            println!("{}: {}", patterns[pattern_idx], line);
+        }
+
+        return Ok(());
+    }
+
+    if config.fuzzy {
+        let f = File::open(config.filename)?;
+        let reader = BufReader::new(f);
+
+        for (line, m) in fuzzy_search(&config.query, reader) {
+            println!("{} (score {})", line, m.score);
+        }
+
+        return Ok(());
+    }
+
     let f = File::open(config.filename)?;
     let reader = BufReader::new(f);
 
-    let results = if config.case_sensitive {
-        if config.use_kmp {
-            kmp_search(&config.query, reader)
+    if config.case_sensitive {
+        let results: Vec<Vec<u8>> = if config.use_kmp {
+            if config.prefilter {
+                kmp_search_prefiltered(&config.query, reader)
+            } else {
+                kmp_search(&config.query, reader)
+            }
         }
         else {
-            search(&config.query, reader)
+            if config.prefilter {
+                search_prefiltered(&config.query, reader)
+            } else {
+                search(&config.query, reader)
+            }
+        };
+        for line in results {
+            println!("{}", to_display_string(&line));
         }
     } else {
-        if config.use_kmp {
-            kmp_search_case_insensitive(&config.query, reader)
+        let match_config = MatchConfig {
+            ignore_case: true,
+            normalize_diacritics: config.normalize_diacritics,
+        };
+        let results = if config.use_kmp {
+            kmp_search_case_insensitive(&config.query, reader, &match_config)
         }
         else {
-            search_case_insensitive(&config.query, reader)
+            search_case_insensitive(&config.query, reader, &match_config)
+        };
+        for line in results {
+            println!("{}", line);
         }
-    };
-    for line in results {
-        println!("{}", line);
     }
 
     Ok(())
 }
 
-pub fn kmp_found(query: &str, line: &str, jump_table: &Vec<usize>) -> bool {
+pub fn kmp_found<N: AsRef<[u8]>, L: AsRef<[u8]>>(query: N, line: L, jump_table: &Vec<usize>) -> bool {
     match kmp::kmp(query, line, &jump_table) {
         Some(_) => true,
         None => false,
     }
 }
 
-pub fn kmp_search<T: BufRead + Sized>(query: &str, reader: T) -> Vec<String> {
-    // index query
-    let jump_table:Vec<usize> = kmp::return_failure_function_table(&query);
+// Like `BufRead::lines()`, but over raw bytes so invalid UTF-8 doesn't error.
+fn read_byte_lines<T: BufRead>(mut reader: T) -> Vec<Vec<u8>> {
+    let mut lines = Vec::new();
+
+    loop {
+        let mut line = Vec::new();
+        let bytes_read = reader.read_until(b'\n', &mut line).unwrap();
+        if bytes_read == 0 {
+            break;
+        }
+
+        if line.last() == Some(&b'\n') {
+            line.pop();
+            if line.last() == Some(&b'\r') {
+                line.pop();
+            }
+        }
+
+        lines.push(line);
+    }
+
+    lines
+}
+
+// Lossily render a raw matched line for display.
+pub fn to_display_string(line: &[u8]) -> String {
+    String::from_utf8_lossy(line).into_owned()
+}
+
+fn contains_bytes(haystack: &[u8], needle: &[u8]) -> bool {
+    if needle.is_empty() {
+        return true;
+    }
+    if haystack.len() < needle.len() {
+        return false;
+    }
+
+    haystack.windows(needle.len()).any(|window| window == needle)
+}
+
+// Rough relative frequency for a byte, used only to guess the rarest byte
+// in a query. Not measured from any corpus - just a plausible ordering.
+fn byte_frequency(byte: u8) -> u32 {
+    match byte {
+        b' ' => 130,
+        b'e' => 120,
+        b't' => 110,
+        b'a' => 100,
+        b'o' => 95,
+        b'i' => 90,
+        b'n' => 85,
+        b's' => 80,
+        b'h' => 75,
+        b'r' => 70,
+        b'\n' => 65,
+        b'd' => 60,
+        b'l' => 55,
+        b'c' => 50,
+        b'u' => 45,
+        b'm' => 40,
+        b'w' => 35,
+        b'f' => 30,
+        b'g' => 28,
+        b'y' => 26,
+        b'p' => 24,
+        b'b' => 20,
+        b'0'..=b'9' => 18,
+        b'v' => 15,
+        b'k' => 12,
+        b'A'..=b'Z' => 10,
+        _ => 5,
+    }
+}
+
+// Pick the rarest byte in `query` and its offset (ties broken by earliest).
+pub fn rare_byte(query: &[u8]) -> (u8, usize) {
+    if query.is_empty() {
+        return (0, 0);
+    }
+
+    let mut best_idx = 0;
+    let mut best_score = u32::MAX;
+    for (idx, &byte) in query.iter().enumerate() {
+        let score = byte_frequency(byte);
+        if score < best_score {
+            best_score = score;
+            best_idx = idx;
+        }
+    }
+
+    (query[best_idx], best_idx)
+}
+
+// Whether `needle` occurs in `haystack` at a position that could host a
+// `query_len`-byte match starting `offset` bytes earlier.
+fn rare_byte_present(haystack: &[u8], needle: u8, offset: usize, query_len: usize) -> bool {
+    if query_len == 0 {
+        return true;
+    }
+    if haystack.len() < query_len {
+        return false;
+    }
+
+    for (i, &byte) in haystack.iter().enumerate() {
+        if byte == needle && i >= offset && haystack.len() - i >= query_len - offset {
+            return true;
+        }
+    }
+
+    false
+}
+
+pub fn search_prefiltered<N: AsRef<[u8]>, T: BufRead + Sized>(query: N, reader: T) -> Vec<Vec<u8>> {
+    let query_bytes = query.as_ref();
+    let (rare, offset) = rare_byte(query_bytes);
 
     let mut result = Vec::new();
+    for line in read_byte_lines(reader) {
+        if !rare_byte_present(&line, rare, offset, query_bytes.len()) {
+            continue;
+        }
+        if contains_bytes(&line, query_bytes) {
+            result.push(line);
+        }
+    }
 
-    // search query in text using index to skip comparisons
-    for line_ in reader.lines() {
-        let line = line_.unwrap();
-        if kmp_found(query, &line, &jump_table)  {
+    result
+}
+
+pub fn kmp_search_prefiltered<N: AsRef<[u8]>, T: BufRead + Sized>(query: N, reader: T) -> Vec<Vec<u8>> {
+    let query_bytes = query.as_ref();
+    let jump_table: Vec<usize> = kmp::return_failure_function_table(query_bytes);
+    let (rare, offset) = rare_byte(query_bytes);
+
+    let mut result = Vec::new();
+    for line in read_byte_lines(reader) {
+        if !rare_byte_present(&line, rare, offset, query_bytes.len()) {
+            continue;
+        }
+        if kmp_found(query_bytes, &line, &jump_table) {
             result.push(line);
         }
     }
@@ -92,32 +287,148 @@ pub fn kmp_search<T: BufRead + Sized>(query: &str, reader: T) -> Vec<String> {
     result
 }
 
-pub fn kmp_search_case_insensitive<T: BufRead + Sized>(query: &str, reader: T) -> Vec<String> {
+pub fn kmp_search<N: AsRef<[u8]>, T: BufRead + Sized>(query: N, reader: T) -> Vec<Vec<u8>> {
+    let query_bytes = query.as_ref();
+
     // index query
-    let jump_table:Vec<usize> = kmp::return_failure_function_table(&query.to_lowercase());
+    let jump_table:Vec<usize> = kmp::return_failure_function_table(query_bytes);
 
     let mut result = Vec::new();
 
     // search query in text using index to skip comparisons
-    for line_ in reader.lines() {
-        let line = line_.unwrap();
-        if kmp_found(&query.to_lowercase(), &line.to_lowercase(), &jump_table)  {
+    for line in read_byte_lines(reader) {
+        if kmp_found(query_bytes, &line, &jump_table)  {
             result.push(line);
         }
     }
 
     result
+}
+
+// Controls how the case-insensitive search functions normalize text before
+// comparing it.
+#[derive(Debug, Clone, Copy)]
+pub struct MatchConfig {
+    pub ignore_case: bool,
+    pub normalize_diacritics: bool,
+}
+
+// Fold a common accented Latin letter or ligature to its unaccented,
+// lowercase form (both 'é' and 'É' become "e").
+fn strip_diacritic(c: char) -> Option<&'static str> {
+    Some(match c {
+        'á' | 'à' | 'â' | 'ä' | 'ã' | 'å' | 'Á' | 'À' | 'Â' | 'Ä' | 'Ã' | 'Å' => "a",
+        'é' | 'è' | 'ê' | 'ë' | 'É' | 'È' | 'Ê' | 'Ë' => "e",
+        'í' | 'ì' | 'î' | 'ï' | 'Í' | 'Ì' | 'Î' | 'Ï' => "i",
+        'ó' | 'ò' | 'ô' | 'ö' | 'õ' | 'Ó' | 'Ò' | 'Ô' | 'Ö' | 'Õ' => "o",
+        'ú' | 'ù' | 'û' | 'ü' | 'Ú' | 'Ù' | 'Û' | 'Ü' => "u",
+        'ñ' | 'Ñ' => "n",
+        'ç' | 'Ç' => "c",
+        'ý' | 'ÿ' | 'Ý' => "y",
+        'ﬁ' => "fi",
+        'ﬂ' => "fl",
+        _ => return None,
+    })
+}
+
+// Decode `text` to Unicode scalars and fold each one per `config`.
+fn normalize_chars(text: &str, config: &MatchConfig) -> Vec<char> {
+    let mut chars = Vec::new();
+    for c in text.chars() {
+        if config.normalize_diacritics {
+            if let Some(replacement) = strip_diacritic(c) {
+                chars.extend(replacement.chars());
+                continue;
+            }
+        }
+        chars.push(c);
+    }
 
+    if config.ignore_case {
+        chars.iter().flat_map(|c| c.to_lowercase()).collect()
+    } else {
+        chars
+    }
 }
 
+fn contains_chars(haystack: &[char], needle: &[char]) -> bool {
+    if needle.is_empty() {
+        return true;
+    }
+    if haystack.len() < needle.len() {
+        return false;
+    }
+
+    haystack.windows(needle.len()).any(|window| window == needle)
+}
+
+// Same recurrence as `kmp::return_failure_function_table`, but over
+// Unicode scalars instead of bytes.
+fn failure_function_chars(needle: &[char]) -> Vec<usize> {
+    let mut jump_table: Vec<usize> = vec![0; needle.len()];
+    let mut i: usize = 1;
+
+    while i < needle.len() {
+        let mut j = i;
+        while j > 0 {
+            if needle[i] == needle[jump_table[j - 1]] {
+                jump_table[i] = 1 + jump_table[i - 1];
+                break;
+            }
+            else {
+                j = jump_table[jump_table[j - 1]];
+            }
+        }
+        i += 1;
+    }
+
+    jump_table
+}
+
+// Same recurrence as `kmp::kmp`, but over Unicode scalars instead of bytes.
+fn kmp_chars(needle: &[char], haystack: &[char], jump_table: &Vec<usize>) -> Option<usize> {
+    let mut i = 0;
+    let mut j = 0;
+    let mut i0 = 0;
+
+    while haystack.len() - i0 >= needle.len() {
+        if j == needle.len() {
+            return Some(i0);
+        }
+
+        if haystack[i] == needle[j] {
+            j += 1;
+            i += 1;
+        }
+        else {
+            if j == 0 {
+                i += 1;
+            }
+            else {
+                j = jump_table[j - 1];
+            }
+
+            i0 = i - j;
+        }
+    }
+
+    None
+}
+
+pub fn kmp_search_case_insensitive<T: BufRead + Sized>(
+    query: &str,
+    reader: T,
+    config: &MatchConfig,
+) -> Vec<String> {
+    let query_chars = normalize_chars(query, config);
+    let jump_table = failure_function_chars(&query_chars);
 
-pub fn search<T: BufRead + Sized>(query: &str, reader: T) -> Vec<String> {
     let mut result = Vec::new();
 
-    // search query in text using index to skip comparisons
     for line_ in reader.lines() {
         let line = line_.unwrap();
-        if line.contains(query)  {
+        let haystack_chars = normalize_chars(&line, config);
+        if kmp_chars(&query_chars, &haystack_chars, &jump_table).is_some() {
             result.push(line);
         }
     }
@@ -125,13 +436,33 @@ pub fn search<T: BufRead + Sized>(query: &str, reader: T) -> Vec<String> {
     result
 }
 
-pub fn search_case_insensitive<T: BufRead + Sized>(query: &str, reader: T) -> Vec<String> {
+
+pub fn search<N: AsRef<[u8]>, T: BufRead + Sized>(query: N, reader: T) -> Vec<Vec<u8>> {
+    let query_bytes = query.as_ref();
     let mut result = Vec::new();
 
     // search query in text using index to skip comparisons
+    for line in read_byte_lines(reader) {
+        if contains_bytes(&line, query_bytes)  {
+            result.push(line);
+        }
+    }
+
+    result
+}
+
+pub fn search_case_insensitive<T: BufRead + Sized>(
+    query: &str,
+    reader: T,
+    config: &MatchConfig,
+) -> Vec<String> {
+    let query_chars = normalize_chars(query, config);
+    let mut result = Vec::new();
+
     for line_ in reader.lines() {
         let line = line_.unwrap();
-        if line.to_lowercase().contains(&query.to_lowercase())  {
+        let haystack_chars = normalize_chars(&line, config);
+        if contains_chars(&haystack_chars, &query_chars) {
             result.push(line);
         }
     }
@@ -139,6 +470,290 @@ pub fn search_case_insensitive<T: BufRead + Sized>(query: &str, reader: T) -> Ve
     result
 }
 
+// A node in the Aho-Corasick trie. `fail` is the KMP-style failure link;
+// `output` holds the indices of every pattern ending here, including ones
+// inherited via `fail`.
+struct AcNode {
+    children: HashMap<u8, usize>,
+    fail: usize,
+    output: Vec<usize>,
+}
+
+// Build the trie, then compute fail links (and merge output links) with a
+// BFS over the trie, root node first.
+fn build_automaton(patterns: &[&str]) -> Vec<AcNode> {
+    let mut nodes = vec![AcNode {
+        children: HashMap::new(),
+        fail: 0,
+        output: Vec::new(),
+    }];
+
+    for (pattern_idx, pattern) in patterns.iter().enumerate() {
+        let mut state = 0;
+        for &byte in pattern.as_bytes() {
+            state = match nodes[state].children.get(&byte) {
+                Some(&next) => next,
+                None => {
+                    nodes.push(AcNode {
+                        children: HashMap::new(),
+                        fail: 0,
+                        output: Vec::new(),
+                    });
+                    let next = nodes.len() - 1;
+                    nodes[state].children.insert(byte, next);
+                    next
+                }
+            };
+        }
+        nodes[state].output.push(pattern_idx);
+    }
+
+    let mut queue = VecDeque::new();
+    let root_children: Vec<usize> = nodes[0].children.values().copied().collect();
+    for child in root_children {
+        nodes[child].fail = 0;
+        queue.push_back(child);
+    }
+
+    while let Some(state) = queue.pop_front() {
+        let children: Vec<(u8, usize)> = nodes[state]
+            .children
+            .iter()
+            .map(|(&byte, &next)| (byte, next))
+            .collect();
+
+        for (byte, next) in children {
+            queue.push_back(next);
+
+            let mut fallback = nodes[state].fail;
+            while fallback != 0 && !nodes[fallback].children.contains_key(&byte) {
+                fallback = nodes[fallback].fail;
+            }
+            nodes[next].fail = match nodes[fallback].children.get(&byte) {
+                Some(&target) if target != next => target,
+                _ => 0,
+            };
+
+            let inherited = nodes[nodes[next].fail].output.clone();
+            nodes[next].output.extend(inherited);
+        }
+    }
+
+    nodes
+}
+
+// Search every line against all of `patterns` in one pass, returning
+// `(pattern_index, line)` once per pattern per line.
+pub fn ac_search<T: BufRead + Sized>(patterns: &[&str], reader: T) -> Vec<(usize, String)> {
+    let nodes = build_automaton(patterns);
+
+    let mut result = Vec::new();
+    for line_ in reader.lines() {
+        let line = line_.unwrap();
+
+        let mut state = 0;
+        let mut matched = HashSet::new();
+
+        // An empty pattern matches every line (including an empty one) at
+        // the root, before any byte is consumed - the byte loop below alone
+        // would not report it on every line (never on an empty one).
+        for &pattern_idx in &nodes[0].output {
+            if matched.insert(pattern_idx) {
+                result.push((pattern_idx, line.clone()));
+            }
+        }
+
+        for &byte in line.as_bytes() {
+            while state != 0 && !nodes[state].children.contains_key(&byte) {
+                state = nodes[state].fail;
+            }
+            state = *nodes[state].children.get(&byte).unwrap_or(&0);
+
+            for &pattern_idx in &nodes[state].output {
+                if matched.insert(pattern_idx) {
+                    result.push((pattern_idx, line.clone()));
+                }
+            }
+        }
+    }
+
+    result
+}
+
+// Base reward per matched character, plus the bonuses/penalties `fuzzy_match`
+// layers on top, fzf-style.
+const FUZZY_SCORE_MATCH: i32 = 16;
+const FUZZY_BONUS_BOUNDARY: i32 = 8;
+const FUZZY_BONUS_CONSECUTIVE: i32 = 4;
+const FUZZY_PENALTY_GAP: i32 = 2;
+
+// Sentinel for "no valid alignment reaches this cell" in the fuzzy DP below.
+// Kept well clear of i32::MIN so penalty subtraction can't wrap.
+const FUZZY_NEG_INF: i32 = i32::MIN / 2;
+
+// Classification of a haystack character, used to detect word boundaries.
+#[derive(Debug, Clone, Copy, PartialEq)]
+enum CharClass {
+    Lower,
+    Upper,
+    Number,
+    Delimiter,
+    Whitespace,
+    NonWord,
+}
+
+fn classify(c: char) -> CharClass {
+    if c.is_whitespace() {
+        CharClass::Whitespace
+    } else if c == '_' || c == '-' || c == '.' || c == '/' || c == ':' {
+        CharClass::Delimiter
+    } else if c.is_ascii_digit() {
+        CharClass::Number
+    } else if c.is_uppercase() {
+        CharClass::Upper
+    } else if c.is_lowercase() {
+        CharClass::Lower
+    } else {
+        CharClass::NonWord
+    }
+}
+
+// Whether haystack index `idx` is a word boundary (start, after a
+// delimiter/whitespace run, or a camelCase transition).
+fn is_boundary(classes: &[CharClass], idx: usize) -> bool {
+    if idx == 0 {
+        return true;
+    }
+    match classes[idx - 1] {
+        CharClass::Delimiter | CharClass::Whitespace => true,
+        CharClass::Lower if classes[idx] == CharClass::Upper => true,
+        _ => false,
+    }
+}
+
+// Result of a successful fuzzy match: the score, and the haystack indices
+// used for each query character, for highlighting.
+#[derive(Debug)]
+pub struct FuzzyMatch {
+    pub score: i32,
+    pub positions: Vec<usize>,
+}
+
+impl PartialEq for FuzzyMatch {
+    fn eq(&self, other: &Self) -> bool {
+        self.score == other.score && self.positions == other.positions
+    }
+}
+
+// Score `query` as a scattered subsequence of `haystack`, fzf-style, or
+// `None` if it doesn't occur as one at all. Case-insensitive.
+pub fn fuzzy_match(query: &str, haystack: &str) -> Option<FuzzyMatch> {
+    let query_chars: Vec<char> = query.chars().collect();
+    let haystack_chars: Vec<char> = haystack.chars().collect();
+
+    let n = query_chars.len();
+    let m = haystack_chars.len();
+
+    if n == 0 {
+        return Some(FuzzyMatch { score: 0, positions: Vec::new() });
+    }
+    if m < n {
+        return None;
+    }
+
+    let classes: Vec<CharClass> = haystack_chars.iter().map(|&c| classify(c)).collect();
+    let folded_query: Vec<char> = query_chars
+        .iter()
+        .map(|&c| c.to_lowercase().next().unwrap_or(c))
+        .collect();
+    let folded_haystack: Vec<char> = haystack_chars
+        .iter()
+        .map(|&c| c.to_lowercase().next().unwrap_or(c))
+        .collect();
+
+    let mut best_score = vec![vec![FUZZY_NEG_INF; m + 1]; n + 1];
+    let mut best_pos: Vec<Vec<Option<usize>>> = vec![vec![None; m + 1]; n + 1];
+    // Whether best_score[i][j] was set by matching haystack[j - 1] against
+    // query[i - 1] here, rather than by carrying forward best_score[i][j - 1].
+    let mut matched_here = vec![vec![false; m + 1]; n + 1];
+
+    for row in best_score[0].iter_mut() {
+        *row = 0;
+    }
+
+    for i in 1..=n {
+        for j in 1..=m {
+            best_score[i][j] = best_score[i][j - 1];
+            best_pos[i][j] = best_pos[i][j - 1];
+
+            if folded_haystack[j - 1] != folded_query[i - 1] {
+                continue;
+            }
+
+            let prev_score = best_score[i - 1][j - 1];
+            if prev_score <= FUZZY_NEG_INF {
+                continue;
+            }
+
+            let mut bonus = FUZZY_SCORE_MATCH;
+            if is_boundary(&classes, j - 1) {
+                bonus += FUZZY_BONUS_BOUNDARY;
+            }
+            match best_pos[i - 1][j - 1] {
+                Some(prev_pos) if prev_pos + 1 == j - 1 => bonus += FUZZY_BONUS_CONSECUTIVE,
+                Some(prev_pos) => bonus -= (j - 1 - prev_pos - 1) as i32 * FUZZY_PENALTY_GAP,
+                None => {}
+            }
+
+            let candidate = prev_score + bonus;
+            if candidate > best_score[i][j] {
+                best_score[i][j] = candidate;
+                best_pos[i][j] = Some(j - 1);
+                matched_here[i][j] = true;
+            }
+        }
+    }
+
+    if best_score[n][m] <= FUZZY_NEG_INF {
+        return None;
+    }
+
+    // Walk the winning path back from (n, m), recording the haystack index
+    // used for each query character along the way.
+    let mut positions = Vec::with_capacity(n);
+    let mut i = n;
+    let mut j = m;
+    while i > 0 {
+        if matched_here[i][j] {
+            positions.push(j - 1);
+            i -= 1;
+            j -= 1;
+        } else {
+            j -= 1;
+        }
+    }
+    positions.reverse();
+
+    Some(FuzzyMatch {
+        score: best_score[n][m],
+        positions,
+    })
+}
+
+// Fuzzy-match `query` against every line, sorted by descending score.
+pub fn fuzzy_search<T: BufRead + Sized>(query: &str, reader: T) -> Vec<(String, FuzzyMatch)> {
+    let mut result = Vec::new();
+    for line_ in reader.lines() {
+        let line = line_.unwrap();
+        if let Some(m) = fuzzy_match(query, &line) {
+            result.push((line, m));
+        }
+    }
+
+    result.sort_by_key(|(_, m)| std::cmp::Reverse(m.score));
+    result
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -152,7 +767,10 @@ safe, fast, productive.
 Pick three.
 Duct tape.";
 
-        assert_eq!(vec!["safe, fast, productive."], search(query, contents))
+        assert_eq!(
+            vec![b"safe, fast, productive.".to_vec()],
+            search(query, contents.as_bytes())
+        )
     }
 
     #[test]
@@ -164,7 +782,60 @@ safe, fast, productive.
 Pick three.
 Duct tape.";
 
-        assert_eq!(vec!["safe, fast, productive."], kmp_search(query, contents))
+        assert_eq!(
+            vec![b"safe, fast, productive.".to_vec()],
+            kmp_search(query, contents.as_bytes())
+        )
+    }
+
+    #[test]
+    fn kmp_search_non_utf8_line() {
+        // 0xFF is not valid UTF-8 on its own - `search`/`kmp_search` must
+        // still find a byte-exact match without erroring on the line.
+        let needle: &[u8] = &[0xFF, b'A'];
+        let haystack: &[u8] = &[b'X', 0xFF, b'A', b'\n', b'Y'];
+        let matching_line = vec![b'X', 0xFF, b'A'];
+        assert_eq!(vec![matching_line.clone()], kmp_search(needle, haystack));
+        assert_eq!(vec![matching_line], search(needle, haystack));
+    }
+
+    #[test]
+    fn prefiltered_search_matches_plain_search() {
+        let query = "duct";
+        let contents = "\
+Rust:
+safe, fast, productive.
+Pick three.
+Duct tape.";
+
+        assert_eq!(
+            vec![b"safe, fast, productive.".to_vec()],
+            search_prefiltered(query, contents.as_bytes())
+        )
+    }
+
+    #[test]
+    fn prefiltered_kmp_search_matches_plain_kmp_search() {
+        let query = "duct";
+        let contents = "\
+Rust:
+safe, fast, productive.
+Pick three.
+Duct tape.";
+
+        assert_eq!(
+            vec![b"safe, fast, productive.".to_vec()],
+            kmp_search_prefiltered(query, contents.as_bytes())
+        )
+    }
+
+    #[test]
+    fn rare_byte_present_rejects_lines_missing_the_byte() {
+        let query = "qzqz";
+        let (rare, offset) = rare_byte(query.as_bytes());
+
+        assert!(!rare_byte_present(b"no rare bytes on this line", rare, offset, query.len()));
+        assert!(rare_byte_present(b"this line has qzqz in it", rare, offset, query.len()));
     }
 
     #[test]
@@ -176,9 +847,13 @@ safe, fast, productive.
 Pick three.
 Trust me.";
 
+        let config = MatchConfig {
+            ignore_case: true,
+            normalize_diacritics: false,
+        };
         assert_eq!(
             vec!["Rust:", "Trust me."],
-            search_case_insensitive(query, contents)
+            search_case_insensitive(query, contents.as_bytes(), &config)
         );
     }
 
@@ -191,10 +866,148 @@ safe, fast, productive.
 Pick three.
 Trust me.";
 
+        let config = MatchConfig {
+            ignore_case: true,
+            normalize_diacritics: false,
+        };
         assert_eq!(
             vec!["Rust:", "Trust me."],
-            kmp_search_case_insensitive(query, contents)
+            kmp_search_case_insensitive(query, contents.as_bytes(), &config)
+        );
+    }
+
+    #[test]
+    fn search_case_insensitive_normalizes_diacritics() {
+        let query = "naive";
+        let contents = "\
+She is quite naïve about this.
+Nothing to see here.";
+
+        let config = MatchConfig {
+            ignore_case: true,
+            normalize_diacritics: true,
+        };
+        assert_eq!(
+            vec!["She is quite naïve about this."],
+            search_case_insensitive(query, contents.as_bytes(), &config)
         );
     }
 
+    #[test]
+    fn kmp_search_case_insensitive_normalizes_diacritics() {
+        let query = "naive";
+        let contents = "\
+She is quite naïve about this.
+Nothing to see here.";
+
+        let config = MatchConfig {
+            ignore_case: true,
+            normalize_diacritics: true,
+        };
+        assert_eq!(
+            vec!["She is quite naïve about this."],
+            kmp_search_case_insensitive(query, contents.as_bytes(), &config)
+        );
+    }
+
+    #[test]
+    fn search_case_insensitive_without_diacritic_normalization_does_not_match() {
+        let query = "naive";
+        let contents = "She is quite naïve about this.";
+
+        let config = MatchConfig {
+            ignore_case: true,
+            normalize_diacritics: false,
+        };
+        assert_eq!(
+            Vec::<String>::new(),
+            search_case_insensitive(query, contents.as_bytes(), &config)
+        );
+    }
+
+    #[test]
+    fn ac_search_multiple_patterns() {
+        let patterns = ["duct", "three", "missing"];
+        let contents = "\
+Rust:
+safe, fast, productive.
+Pick three.
+Duct tape.";
+
+        assert_eq!(
+            vec![(0, String::from("safe, fast, productive.")), (1, String::from("Pick three."))],
+            ac_search(&patterns, contents.as_bytes())
+        );
+    }
+
+    #[test]
+    fn ac_search_overlapping_patterns_same_line() {
+        let patterns = ["he", "she", "hers"];
+        let contents = "ushers";
+
+        assert_eq!(
+            vec![
+                (1, String::from("ushers")),
+                (0, String::from("ushers")),
+                (2, String::from("ushers")),
+            ],
+            ac_search(&patterns, contents.as_bytes())
+        );
+    }
+
+    #[test]
+    fn ac_search_empty_pattern_matches_every_line() {
+        let patterns = ["missing", ""];
+        let contents = "\
+one
+two";
+
+        assert_eq!(
+            vec![
+                (1, String::from("one")),
+                (1, String::from("two")),
+            ],
+            ac_search(&patterns, contents.as_bytes())
+        );
+    }
+
+    #[test]
+    fn fuzzy_match_scattered_subsequence() {
+        let result = fuzzy_match("fzf", "fuzzy_finder").unwrap();
+        assert_eq!(vec![0, 2, 6], result.positions);
+    }
+
+    #[test]
+    fn fuzzy_match_rejects_non_subsequence() {
+        assert_eq!(None, fuzzy_match("xyz", "fuzzy_finder"));
+    }
+
+    #[test]
+    fn fuzzy_match_prefers_boundary_and_consecutive_matches() {
+        // "fi" matches both the "fi" at the start of "file_item" (a
+        // boundary, then consecutive) and the scattered "f...i" further in
+        // "far index" - the boundary/consecutive run should score higher.
+        let boundary = fuzzy_match("fi", "file_item").unwrap();
+        let scattered = fuzzy_match("fi", "far index").unwrap();
+        assert!(boundary.score > scattered.score);
+    }
+
+    #[test]
+    fn fuzzy_match_is_case_insensitive() {
+        let result = fuzzy_match("FZF", "fuzzy_finder").unwrap();
+        assert_eq!(vec![0, 2, 6], result.positions);
+    }
+
+    #[test]
+    fn fuzzy_search_sorts_by_descending_score() {
+        let contents = "\
+far index
+fuzzy_finder
+nothing here";
+
+        let results = fuzzy_search("fi", contents.as_bytes());
+        let lines: Vec<&str> = results.iter().map(|(line, _)| line.as_str()).collect();
+        assert_eq!(vec!["far index", "fuzzy_finder"], lines);
+    }
+
 }
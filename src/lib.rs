@@ -1,20 +1,21 @@
 // To Do:
-// 1.) Using .as_bytes() to get index access to strings, explore for alternatives in Rust
-//     Related: Use generics to accept &str or Vec<u8> (bytes)
 // 2.) Test and handle weird input, e.g. empty strings
-//     Related: Extend code to any UTF-8 encoded string. Focus right now on Biological Strings
-
 
 // Return a vector containing the KMP failure function values
 // Value at index i corresponds to the value for the prefix of length (i + 1)
 // i.e. i = 0 corresponds to needle[0] (is always 0) and i = len(needle) - 1 corresponds to needle
-pub fn return_failure_function_table(needle: &str) -> Vec<usize> {
+//
+// Generic over `AsRef<[u8]>` so callers can index a `&str`, `&[u8]`, or
+// `Vec<u8>` without forcing a (possibly lossy) UTF-8 round-trip - useful for
+// non-UTF-8 byte streams like FASTQ quality lines.
+pub fn return_failure_function_table<N: AsRef<[u8]>>(needle: N) -> Vec<usize> {
+
+    // init needle as bytes for index access
+    let needle_bytes = needle.as_ref();
 
     // init jump table - idx represents jump for prefix of length (idx + 1)
-    let mut jump_table: Vec<usize> = vec![0; needle.len()];
+    let mut jump_table: Vec<usize> = vec![0; needle_bytes.len()];
 
-    // init needle as bytes for index access
-    let needle_bytes = needle.as_bytes();
     let mut i:usize = 1;
 
     // loop over needle and compute jumps for each prefix size
@@ -35,17 +36,17 @@ pub fn return_failure_function_table(needle: &str) -> Vec<usize> {
     jump_table
 }
 
-pub fn kmp_wrapper(needle: &str, haystack: &str) -> Option<usize> {
+pub fn kmp_wrapper<N: AsRef<[u8]>, H: AsRef<[u8]>>(needle: N, haystack: H) -> Option<usize> {
 
     // Index needle - i.e. init jumps for each prefix size of needle
-    let jump_table = return_failure_function_table(&needle);
+    let jump_table = return_failure_function_table(needle.as_ref());
 
     // KMP search
     kmp(needle, haystack, &jump_table)
 }
 
 // Return idx in haystack where the first occurence of needle occurs
-pub fn kmp(needle: &str, haystack: &str, jump_table: &Vec<usize>) -> Option<usize> {
+pub fn kmp<N: AsRef<[u8]>, H: AsRef<[u8]>>(needle: N, haystack: H, jump_table: &Vec<usize>) -> Option<usize> {
     // Search for needle in haystack using jump_table to skip unwanted comparisons
     // idx in haystack
     let mut i = 0;
@@ -54,8 +55,8 @@ pub fn kmp(needle: &str, haystack: &str, jump_table: &Vec<usize>) -> Option<usiz
     // start idx for match in needle
     let mut i0 = 0;
 
-    let haystack_bytes = haystack.as_bytes();
-    let needle_bytes = needle.as_bytes();
+    let haystack_bytes = haystack.as_ref();
+    let needle_bytes = needle.as_ref();
 
     while haystack_bytes.len() - i0 >= needle_bytes.len(){
         if j == needle_bytes.len() {
@@ -199,5 +200,13 @@ mod tests {
                    kmp_wrapper("AGCATTCAAAGAAATTTCC", "AGCATTCAAAGAAATTT"));
     }
 
+    #[test]
+    fn kmp_search_non_utf8_bytes() {
+        // 0xFF/0xFE are not valid UTF-8 on their own, so this couldn't be
+        // searched as a &str without lossy conversion.
+        let needle: &[u8] = &[0xFF, 0xFE];
+        let haystack: &[u8] = &[0x41, 0x42, 0xFF, 0xFE, 0x43];
+        assert_eq!(std::option::Option::Some(2), kmp_wrapper(needle, haystack));
+    }
 
 }